@@ -11,7 +11,12 @@
 //! The `read!()` macro will always read until the next ascii whitespace character
 //! (`\n`, `\r`, `\t` or space).
 //!
-//! Any type that implements the `FromStr` trait can be read with the `read!` macro.
+//! Any type that implements the `FromStr` trait can be read with the `read!` macro,
+//! as long as it also implements [`ReadRadix`] — trivially, by implementing it to
+//! reject a radix the way this crate's own impls for `String`, `char`, `bool`, `f32`
+//! and `f64` do, since that's what lets a single capture and a `{*}`-repeated `Vec`
+//! of them implement the one `Capture` trait without conflicting impls. See the
+//! [`Capture`] trait docs for the full story.
 //!
 //! # Advanced
 //! Text parsing can be done similar to `println!` by adding a format string
@@ -29,23 +34,288 @@
 //! the format string will result in a panic.
 //!
 //! Note: only a single value can be read per `read!` invocation.
+//!
+//! # Format specifiers
+//! A capture can carry a `println!`-style specifier after a colon:
+//!
+//! ```rust,no_run
+//! # #[macro_use]
+//! # extern crate text_io;
+//! # fn main() {
+//! let five_bytes: String = read!("{:5}");
+//! let hex: i32 = read!("{:x}");
+//! # }
+//! ```
+//!
+//! `{:5}` reads exactly five bytes instead of stopping at whitespace, and
+//! `{:x}` / `{:o}` / `{:b}` parse the capture as a hexadecimal, octal or
+//! binary integer instead of decimal.
+//!
+//! A `{*}` placeholder reads a whitespace-separated run of values into a
+//! `Vec<T>`, stopping at end-of-input or at the next literal character in
+//! the format string:
+//!
+//! ```rust,no_run
+//! # #[macro_use]
+//! # extern crate text_io;
+//! # fn main() {
+//! let v: Vec<i32> = read!("{*}");
+//! # }
+//! ```
+//!
+//! # Diagnostics
+//! Parse errors carry the position in the input at which they were
+//! produced. `Error::at` returns it, and it's included in `Display`:
+//!
+//! ```rust,no_run
+//! # #[macro_use]
+//! # extern crate text_io;
+//! # fn main() {
+//! let i: Result<i32, _> = try_read!("{}", "nope".bytes());
+//! if let Err(e) = i {
+//!     println!("{}", e); // "... at line 1, column 5"
+//! }
+//! # }
+//! ```
+//!
+//! # Leading whitespace
+//! A `{ }` placeholder (a space between the braces) behaves like `{}` but
+//! additionally skips leading whitespace even though a literal character
+//! follows it in the format string, so formats like `"x ={ };"` tolerate
+//! extra spaces before the value:
+//!
+//! ```rust,no_run
+//! # #[macro_use]
+//! # extern crate text_io;
+//! # fn main() {
+//! let x: i32 = read!("x ={ };");
+//! # }
+//! ```
 
+use std::any::{Any, TypeId};
 use std::error;
 use std::fmt;
 use std::str::FromStr;
 
+/// A parsed format specifier for a capture, e.g. the `5` in `{:5}` (a fixed
+/// width, in bytes) or the `x`/`o`/`b` in `{:x}`/`{:o}`/`{:b}` (a radix).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Spec {
+    pub width: Option<usize>,
+    pub radix: Option<u32>,
+}
+
+/// Implemented for capture targets that can be parsed given an explicit
+/// radix, the way the `{:x}`/`{:o}`/`{:b}` specifiers do.
+///
+/// The integer primitives delegate to their inherent `from_str_radix`.
+/// Other `FromStr` types still implement this trait, but report an error if
+/// a radix is actually requested. The radix specifiers only consult this
+/// crate's own impls (see [`maybe_read_radix`]): a radix capture into any
+/// other type fails with `Error::Parse`, whatever its `read_radix` does.
+/// [`parse_capture`] itself doesn't need `T: ReadRadix`; this trait exists so
+/// [`Capture`] has a closed bound it can share between a single value and a
+/// `Vec` of them without conflicting impls; see its docs for why.
+pub trait ReadRadix: Sized {
+    fn read_radix(src: &str, radix: u32) -> Result<Self, String>;
+}
+
+macro_rules! impl_read_radix_for_ints {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl ReadRadix for $t {
+                fn read_radix(src: &str, radix: u32) -> Result<Self, String> {
+                    <$t>::from_str_radix(src, radix).map_err(|e| e.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_read_radix_for_ints!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_read_radix_unsupported {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl ReadRadix for $t {
+                fn read_radix(_src: &str, _radix: u32) -> Result<Self, String> {
+                    Err("this type has no radix representation".to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_read_radix_unsupported!(String, char, bool, f32, f64);
+
+/// Tries `src.read_radix(radix)` for the concrete type `U`, but only if `U`
+/// actually is the capture target `T` — letting callers that only know `T`
+/// (not `U`) ask "does *this* type happen to support a radix?" without
+/// requiring `T: ReadRadix` itself.
+fn try_read_radix_as<U: ReadRadix + 'static, T: 'static>(
+    src: &str,
+    radix: u32,
+) -> Option<Result<T, String>> {
+    if TypeId::of::<U>() != TypeId::of::<T>() {
+        return None;
+    }
+    let result: Box<dyn Any> = Box::new(U::read_radix(src, radix));
+    Some(*result.downcast::<Result<T, String>>().unwrap())
+}
+
+/// Reads `src` as a radix-`radix` number if `T` is one of the types this
+/// crate implements [`ReadRadix`] for, or reports that it isn't otherwise.
+///
+/// This lets `{:x}`/`{:o}`/`{:b}` stay available to the closed set of types
+/// that support them without [`parse_capture`] requiring its target to
+/// implement `ReadRadix` — it only needs `T: FromStr`.
+fn maybe_read_radix<T: 'static>(src: &str, radix: u32) -> Result<T, String> {
+    macro_rules! try_types {
+        ($($t:ty),* $(,)?) => {
+            $(
+                if let Some(result) = try_read_radix_as::<$t, T>(src, radix) {
+                    return result;
+                }
+            )*
+        };
+    }
+    try_types!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, String, char, bool, f32,
+        f64
+    );
+    Err("this type has no radix representation".to_string())
+}
+
+/// A location in the input an `Error` was produced at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An input byte iterator that knows its own [`Position`].
+///
+/// `match_next` and `parse_capture` take this instead of a bare
+/// `Iterator<Item = u8>` so they can stamp the errors they produce with
+/// where in the input they happened.
+pub trait PositionedIter: Iterator<Item = u8> {
+    fn location(&self) -> Position;
+}
+
+/// Wraps a byte iterator to track the byte offset and line/column of the
+/// next byte it will yield.
+pub struct CountingIter<'a> {
+    inner: &'a mut dyn Iterator<Item = u8>,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> CountingIter<'a> {
+    pub fn new(inner: &'a mut dyn Iterator<Item = u8>) -> Self {
+        CountingIter {
+            inner,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl<'a> Iterator for CountingIter<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.inner.next();
+        if let Some(b) = byte {
+            self.offset += 1;
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        byte
+    }
+}
+
+impl<'a> PositionedIter for CountingIter<'a> {
+    fn location(&self) -> Position {
+        Position {
+            offset: self.offset,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+/// A single-item-of-lookahead wrapper around a [`PositionedIter`], used by
+/// `{*}` captures to stop before consuming the format string's next
+/// literal byte.
+pub struct Peeking<'a> {
+    iter: &'a mut dyn PositionedIter,
+    peeked: Option<u8>,
+}
+
+impl<'a> Peeking<'a> {
+    pub fn new(iter: &'a mut dyn PositionedIter) -> Self {
+        Peeking { iter, peeked: None }
+    }
+
+    pub fn peek(&mut self) -> Option<u8> {
+        if self.peeked.is_none() {
+            self.peeked = self.iter.next();
+        }
+        self.peeked
+    }
+}
+
+impl<'a> Iterator for Peeking<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.peeked.take().or_else(|| self.iter.next())
+    }
+}
+
+impl<'a> PositionedIter for Peeking<'a> {
+    fn location(&self) -> Position {
+        self.iter.location()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     MissingMatch,
     MissingClosingBrace,
-    UnexpectedValue(u8, Option<u8>),
-    InvalidUtf8(Vec<u8>),
-    PartialUtf8(usize, Vec<u8>),
-    Parse(String, &'static str),
+    UnexpectedValue(u8, Option<u8>, Position),
+    InvalidUtf8(Vec<u8>, Position),
+    PartialUtf8(usize, Vec<u8>, Position),
+    Parse(String, &'static str, Spec, Position),
     #[doc(hidden)]
     __NonExhaustive__,
 }
 
+impl Error {
+    /// The position in the input this error was produced at, if any.
+    ///
+    /// `MissingMatch` and `MissingClosingBrace` describe a problem with the
+    /// format string itself rather than the input, so they have no position.
+    pub fn at(&self) -> Option<Position> {
+        use crate::Error::*;
+
+        match *self {
+            UnexpectedValue(_, _, pos) => Some(pos),
+            InvalidUtf8(_, pos) => Some(pos),
+            PartialUtf8(_, _, pos) => Some(pos),
+            Parse(_, _, _, pos) => Some(pos),
+            _ => None,
+        }
+    }
+}
+
 impl error::Error for Error {
     fn description(&self) -> &str {
         use crate::Error::*;
@@ -68,61 +338,329 @@ impl fmt::Display for Error {
         use std::str::from_utf8;
 
         match *self {
-            InvalidUtf8(ref raw) => write!(f, "input was not valid utf8: {:?}", raw),
-            Parse(ref s, arg) => write!(f, "could not parse {} as target type of {}", s, arg),
-            UnexpectedValue(exp, act) => write!(
+            InvalidUtf8(ref raw, pos) => write!(
                 f,
-                "found value {:?} not matching the pattern value {}",
+                "input was not valid utf8: {:?} at line {}, column {}",
+                raw, pos.line, pos.column
+            ),
+            Parse(ref s, arg, spec, pos) => write!(
+                f,
+                "could not parse {} as target type of {} (spec: {:?}) at line {}, column {}",
+                s, arg, spec, pos.line, pos.column
+            ),
+            UnexpectedValue(exp, act, pos) => write!(
+                f,
+                "found value {:?} not matching the pattern value {} at line {}, column {}",
                 act.map(|b| b as char),
-                exp as char
+                exp as char,
+                pos.line,
+                pos.column
             ),
-            PartialUtf8(n, ref raw) => write!(
+            PartialUtf8(n, ref raw, pos) => write!(
                 f,
-                "input was only partially valid utf8: \"{}\" followed by {:?}",
+                "input was only partially valid utf8: \"{}\" followed by {:?} at line {}, column {}",
                 from_utf8(&raw[..n]).unwrap(),
-                &raw[n..]
+                &raw[n..],
+                pos.line,
+                pos.column
             ),
             _ => write!(f, "{}", <Error as error::Error>::description(self)),
         }
     }
 }
 
-pub fn match_next(expected: u8, iter: &mut dyn Iterator<Item = u8>) -> Result<(), Error> {
+pub fn match_next(expected: u8, iter: &mut dyn PositionedIter) -> Result<(), Error> {
     let next = iter.next();
     if next != Some(expected) {
-        return Err(Error::UnexpectedValue(expected, next))?;
+        return Err(Error::UnexpectedValue(expected, next, iter.location()))?;
     }
     Ok(())
 }
 
+static WHITESPACES: &'static [u8] = b"\t\r\n ";
+
+fn is_whitespace(b: u8) -> bool {
+    WHITESPACES.contains(&b)
+}
+
+/// Controls how a capture finds the boundaries of the text it reads.
+///
+/// The default reproduces the historical, hard-coded behavior of
+/// `parse_capture`: a literal delimiter stops the capture with no leading
+/// whitespace handling, and the absence of one falls back to splitting on
+/// [`WHITESPACES`].
+///
+/// `read!`/`scan!`/`try_read!`/`try_scan!` have no format-string syntax for
+/// `delims` (only `skip_leading_ws`, via `{ }`) and always pass the default,
+/// but callers of [`parse_capture`] directly can supply their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureOpts {
+    /// Skip leading whitespace before reading, even when a literal
+    /// delimiter follows the capture in the format string.
+    pub skip_leading_ws: bool,
+    /// The byte set a delimiter-less capture stops at (and skips, as
+    /// leading whitespace, before reading).
+    pub delims: &'static [u8],
+}
+
+impl Default for CaptureOpts {
+    fn default() -> Self {
+        CaptureOpts {
+            skip_leading_ws: false,
+            delims: WHITESPACES,
+        }
+    }
+}
+
+/// Number of bytes a UTF-8 code point starting with `lead` should occupy, or
+/// `None` if `lead` can't legally start a code point (e.g. a stray
+/// continuation byte).
+fn utf8_seq_len(lead: u8) -> Option<usize> {
+    if lead & 0x80 == 0x00 {
+        Some(1)
+    } else if lead & 0xe0 == 0xc0 {
+        Some(2)
+    } else if lead & 0xf0 == 0xe0 {
+        Some(3)
+    } else if lead & 0xf8 == 0xf0 {
+        Some(4)
+    } else {
+        None
+    }
+}
+
 pub fn parse_capture<T>(
     name: &'static str,
     next: Option<u8>,
-    iter: &mut dyn Iterator<Item = u8>,
+    iter: &mut dyn PositionedIter,
+    spec: Spec,
+    opts: CaptureOpts,
 ) -> Result<T, Error>
 where
-    T: FromStr,
+    T: FromStr + 'static,
     <T as FromStr>::Err: ::std::fmt::Debug,
 {
-    static WHITESPACES: &'static [u8] = b"\t\r\n ";
-    let raw: Vec<u8> = match next {
-        Some(c) => iter.take_while(|&ch| ch != c).collect(),
-        None => iter
-            .skip_while(|ch| WHITESPACES.contains(ch))
-            .take_while(|ch| !WHITESPACES.contains(ch))
-            .collect(),
+    // Reborrow rather than move `iter` into the adapter chain below so it's
+    // still available afterwards to report the position of any error.
+    let bytes: Box<dyn Iterator<Item = u8> + '_> = match spec.width {
+        Some(width) => Box::new((&mut *iter).take(width)),
+        None => match next {
+            Some(c) if opts.skip_leading_ws => Box::new(
+                (&mut *iter)
+                    .skip_while(|&ch| is_whitespace(ch))
+                    .take_while(move |&ch| ch != c),
+            ),
+            Some(c) => Box::new((&mut *iter).take_while(move |&ch| ch != c)),
+            None => Box::new(
+                (&mut *iter)
+                    .skip_while(move |&ch| opts.delims.contains(&ch))
+                    .take_while(move |&ch| !opts.delims.contains(&ch)),
+            ),
+        },
     };
-    match String::from_utf8(raw) {
-        Ok(s) => FromStr::from_str(&s).map_err(|_| Error::Parse(s, name)),
-        Err(e) => {
-            let n = e.utf8_error().valid_up_to();
-            let raw = e.into_bytes();
-            if n == 0 {
-                Err(Error::InvalidUtf8(raw))
-            } else {
-                Err(Error::PartialUtf8(n, raw))
+
+    // `bytes` borrows `iter`; decoding it to completion here (rather than
+    // passing both into one call) lets that borrow end before we ask
+    // `iter` for the position of any error below.
+    let decoded = decode(bytes);
+    let pos = iter.location();
+    decoded.into_result(name, spec, pos)
+}
+
+/// The result of incrementally UTF-8-decoding a capture, before it's known
+/// where (if anywhere) to attach a [`Position`] to a failure.
+enum Decoded {
+    Valid(String),
+    Invalid(Vec<u8>),
+    Partial(usize, Vec<u8>),
+}
+
+impl Decoded {
+    fn into_result<T>(self, name: &'static str, spec: Spec, pos: Position) -> Result<T, Error>
+    where
+        T: FromStr + 'static,
+        <T as FromStr>::Err: ::std::fmt::Debug,
+    {
+        let s = match self {
+            Decoded::Valid(s) => s,
+            Decoded::Invalid(raw) => return Err(Error::InvalidUtf8(raw, pos)),
+            Decoded::Partial(n, raw) => return Err(Error::PartialUtf8(n, raw, pos)),
+        };
+        match spec.radix {
+            Some(radix) => {
+                maybe_read_radix(&s, radix).map_err(|_| Error::Parse(s, name, spec, pos))
+            }
+            None => FromStr::from_str(&s).map_err(|_| Error::Parse(s, name, spec, pos)),
+        }
+    }
+}
+
+/// Incrementally UTF-8-decodes `bytes` into a `String`.
+///
+/// The valid prefix streams straight into the `String` with no intermediate
+/// buffer; only once a byte actually breaks UTF-8 validity do we start
+/// collecting the (by then unavoidably backtracked) bad tail, so a
+/// successful capture never pays for a second, separate byte buffer.
+fn decode(bytes: impl Iterator<Item = u8>) -> Decoded {
+    let mut s = String::new();
+    let mut pending: [u8; 4] = [0; 4];
+    let mut pending_len: usize = 0;
+    let mut seq_len: usize = 0;
+    let mut bad: Option<Vec<u8>> = None;
+
+    for byte in bytes {
+        if let Some(tail) = bad.as_mut() {
+            tail.push(byte);
+            continue;
+        }
+        if pending_len == 0 {
+            match utf8_seq_len(byte) {
+                Some(n) => {
+                    seq_len = n;
+                    pending[0] = byte;
+                    pending_len = 1;
+                }
+                None => {
+                    bad = Some(vec![byte]);
+                    continue;
+                }
+            }
+        } else if byte & 0xc0 == 0x80 {
+            pending[pending_len] = byte;
+            pending_len += 1;
+        } else {
+            let mut tail = pending[..pending_len].to_vec();
+            tail.push(byte);
+            bad = Some(tail);
+            continue;
+        }
+        if pending_len == seq_len {
+            match ::std::str::from_utf8(&pending[..pending_len]) {
+                Ok(ch) => {
+                    s.push_str(ch);
+                    pending_len = 0;
+                    seq_len = 0;
+                }
+                Err(_) => bad = Some(pending[..pending_len].to_vec()),
+            }
+        }
+    }
+
+    if bad.is_some() || pending_len != 0 {
+        let valid_up_to = s.len();
+        let mut raw = s.into_bytes();
+        match bad {
+            Some(tail) => raw.extend(tail),
+            None => raw.extend_from_slice(&pending[..pending_len]),
+        }
+        return if valid_up_to == 0 {
+            Decoded::Invalid(raw)
+        } else {
+            Decoded::Partial(valid_up_to, raw)
+        };
+    }
+
+    Decoded::Valid(s)
+}
+
+/// Implemented for every capture target, dispatching between a single
+/// value (`{}`, `{:..}`) and a repeated run of values (`{*}`).
+///
+/// The `try_scan!` expansion for a single placeholder always contains both
+/// a "one" call and a "many" call, whichever of the two the format string
+/// actually uses at runtime, so both must type-check for that placeholder's
+/// target type. Routing them through one trait keeps that bound to a
+/// single `T: Capture`, rather than requiring every target to separately
+/// satisfy `parse_capture`'s `FromStr` bound *and* a
+/// collection's "many" bound — which `Vec<T>` could never do, since this
+/// crate can't implement the foreign `FromStr` for the foreign `Vec<T>`.
+pub trait Capture: Sized {
+    fn capture_one(
+        name: &'static str,
+        next: Option<u8>,
+        iter: &mut dyn PositionedIter,
+        spec: Spec,
+        opts: CaptureOpts,
+    ) -> Result<Self, Error>;
+
+    fn capture_many(name: &'static str, next: Option<u8>, iter: &mut Peeking) -> Result<Self, Error>;
+}
+
+impl<T> Capture for T
+where
+    T: FromStr + ReadRadix + 'static,
+    <T as FromStr>::Err: ::std::fmt::Debug,
+{
+    fn capture_one(
+        name: &'static str,
+        next: Option<u8>,
+        iter: &mut dyn PositionedIter,
+        spec: Spec,
+        opts: CaptureOpts,
+    ) -> Result<Self, Error> {
+        parse_capture(name, next, iter, spec, opts)
+    }
+
+    fn capture_many(name: &'static str, _next: Option<u8>, iter: &mut Peeking) -> Result<Self, Error> {
+        Err(Error::Parse(
+            String::new(),
+            name,
+            Spec::default(),
+            iter.location(),
+        ))
+    }
+}
+
+impl<T> Capture for Vec<T>
+where
+    T: FromStr + ReadRadix + 'static,
+    <T as FromStr>::Err: ::std::fmt::Debug,
+{
+    fn capture_one(
+        name: &'static str,
+        _next: Option<u8>,
+        iter: &mut dyn PositionedIter,
+        _spec: Spec,
+        _opts: CaptureOpts,
+    ) -> Result<Self, Error> {
+        Err(Error::Parse(
+            String::new(),
+            name,
+            Spec::default(),
+            iter.location(),
+        ))
+    }
+
+    fn capture_many(name: &'static str, next: Option<u8>, iter: &mut Peeking) -> Result<Self, Error> {
+        let mut values = Vec::new();
+        loop {
+            while matches!(iter.peek(), Some(b) if is_whitespace(b)) {
+                iter.next();
+            }
+            match iter.peek() {
+                None => break,
+                Some(b) if Some(b) == next => break,
+                _ => {}
             }
+            // Each element stops at whitespace (the separator between
+            // elements) *or* at the format string's own terminator byte, so
+            // a value directly abutting that terminator (e.g. "3;" in
+            // "1 2 3;" read by "{*};") doesn't swallow it. This can't be a
+            // plain `take_while`: that adapter still calls `next()` on the
+            // byte it rejects, which would permanently consume the
+            // terminator out of `iter` even though `take_while` itself
+            // doesn't yield it. Peeking first and only advancing on a byte
+            // that passes the predicate leaves it in place for the
+            // `match_next` that runs after this loop.
+            let bytes = std::iter::from_fn(|| match iter.peek() {
+                Some(ch) if !is_whitespace(ch) && Some(ch) != next => iter.next(),
+                _ => None,
+            });
+            let decoded = decode(bytes);
+            let pos = iter.location();
+            values.push(decoded.into_result(name, Spec::default(), pos)?);
         }
+        Ok(values)
     }
 }
 
@@ -186,7 +724,6 @@ macro_rules! try_scan(
     ($pattern:expr, $($arg:expr),*) => {
         use ::std::io::Read;
         try_scan!(::std::io::stdin().bytes().map(std::result::Result::unwrap) => $pattern, $($arg),*);
-        format_args!($pattern, $($arg),*);
     };
     ($input:expr => $pattern:expr, $($arg:expr),*) => {{
         try_scan!(@impl question_mark; $input => $pattern, $($arg),*)
@@ -198,11 +735,13 @@ macro_rules! try_scan(
         ($($e)+).unwrap()
     }};
     (@impl $action:tt; $input:expr => $pattern:expr, $($arg:expr),*) => {{
-        use $crate::{Error, match_next, parse_capture};
+        use $crate::{match_next, Capture, CaptureOpts, Error, Spec};
 
         // typesafe macros :)
         let pattern: &'static str = $pattern;
-        let stdin: &mut Iterator<Item = u8> = &mut ($input);
+        let stdin: &mut dyn Iterator<Item = u8> = &mut ($input);
+        let mut counting = $crate::CountingIter::new(stdin);
+        let mut stdin = $crate::Peeking::new(&mut counting);
 
         let mut pattern = pattern.bytes();
 
@@ -210,20 +749,83 @@ macro_rules! try_scan(
             $arg = loop {
                 match try_scan!(@$action: pattern.next().ok_or(Error::MissingMatch)) {
                     b'{' => match try_scan!(@$action: pattern.next().ok_or(Error::MissingClosingBrace)) {
-                        b'{' => try_scan!(@$action: match_next(b'{', stdin)),
-                        b'}' => break try_scan!(@$action: parse_capture(stringify!($arg), pattern.next(), stdin)),
+                        b'{' => try_scan!(@$action: match_next(b'{', &mut stdin)),
+                        b'}' => break try_scan!(@$action: <_ as Capture>::capture_one(
+                            stringify!($arg),
+                            pattern.next(),
+                            &mut stdin,
+                            Spec::default(),
+                            CaptureOpts::default()
+                        )),
+                        b' ' => match try_scan!(@$action: pattern.next().ok_or(Error::MissingClosingBrace)) {
+                            b'}' => break try_scan!(@$action: <_ as Capture>::capture_one(
+                                stringify!($arg),
+                                pattern.next(),
+                                &mut stdin,
+                                Spec::default(),
+                                CaptureOpts {
+                                    skip_leading_ws: true,
+                                    ..CaptureOpts::default()
+                                }
+                            )),
+                            _ => return try_scan!(@$action: Err(Error::MissingClosingBrace)),
+                        },
+                        b':' => {
+                            let mut width: Option<usize> = None;
+                            let mut radix: Option<u32> = None;
+                            loop {
+                                match try_scan!(@$action: pattern.next().ok_or(Error::MissingClosingBrace)) {
+                                    b'}' => break,
+                                    b'x' => radix = Some(16),
+                                    b'o' => radix = Some(8),
+                                    b'b' => radix = Some(2),
+                                    d @ b'0'..=b'9' => {
+                                        width = Some(width.unwrap_or(0) * 10 + (d - b'0') as usize);
+                                    }
+                                    _ => return try_scan!(@$action: Err(Error::MissingClosingBrace)),
+                                }
+                            }
+                            break try_scan!(@$action: <_ as Capture>::capture_one(
+                                stringify!($arg),
+                                pattern.next(),
+                                &mut stdin,
+                                Spec { width, radix },
+                                CaptureOpts::default()
+                            ));
+                        }
+                        b'*' => match try_scan!(@$action: pattern.next().ok_or(Error::MissingClosingBrace)) {
+                            b'}' => {
+                                // A `{*}` capture already splits on whitespace between its
+                                // own elements, so any whitespace literally following it in
+                                // the format string is just a separator, not something a
+                                // single byte of input needs to match: consume it from the
+                                // pattern (it plays no further role) while leaving the first
+                                // non-whitespace byte in place for the literal-matching loop
+                                // below, which still needs to match it against the input.
+                                let mut probe = pattern.clone();
+                                let mut next = probe.next();
+                                while let Some(b'\t') | Some(b'\r') | Some(b'\n') | Some(b' ') = next {
+                                    pattern.next();
+                                    next = probe.next();
+                                }
+                                break try_scan!(@$action: <_ as Capture>::capture_many(
+                                    stringify!($arg),
+                                    next,
+                                    &mut stdin
+                                ))
+                            }
+                            _ => return try_scan!(@$action: Err(Error::MissingClosingBrace)),
+                        },
                         _ => return try_scan!(@$action: Err(Error::MissingClosingBrace)),
                     },
-                    c => try_scan!(@$action: match_next(c, stdin)),
+                    c => try_scan!(@$action: match_next(c, &mut stdin)),
                 }
             };
         )*
 
         for c in pattern {
-            try_scan!(@$action: match_next(c, stdin))
+            try_scan!(@$action: match_next(c, &mut stdin))
         }
-
-        format_args!($pattern, $($arg),*);
     }};
 );
 
@@ -241,9 +843,116 @@ macro_rules! scan(
     ($text:expr, $($arg:expr),*) => {
         use ::std::io::Read;
         scan!(::std::io::stdin().bytes().map(std::result::Result::unwrap) => $text, $($arg),*);
-        format_args!($text, $($arg),*);
     };
     ($input:expr => $pattern:expr, $($arg:expr),*) => {{
         try_scan!(@impl unwrap; $input => $pattern, $($arg),*)
     }};
 );
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    #[test]
+    fn reads_plain_value() {
+        let i: i32 = try_read!("{}", "42".bytes()).unwrap();
+        assert_eq!(i, 42);
+    }
+
+    #[test]
+    fn width_and_radix_specifiers() {
+        let w: String = try_read!("{:3}", "abcdef".bytes()).unwrap();
+        assert_eq!(w, "abc");
+
+        let hex: i32 = try_read!("{:x}", "2a".bytes()).unwrap();
+        assert_eq!(hex, 42);
+
+        let oct: i32 = try_read!("{:o}", "52".bytes()).unwrap();
+        assert_eq!(oct, 42);
+
+        let bin: i32 = try_read!("{:b}", "101010".bytes()).unwrap();
+        assert_eq!(bin, 42);
+    }
+
+    #[test]
+    fn repetition_capture_splits_on_whitespace() {
+        let v: Vec<i32> = try_read!("{*}", "1 2 3".bytes()).unwrap();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn repetition_capture_stops_before_trailing_literal() {
+        // The trailing `;` must bound the last element ("3") without being
+        // swallowed by it, and must still be there for `{*}`'s implicit
+        // literal match afterward.
+        let v: Vec<i32> = try_read!("{*};", "1 2 3;".bytes()).unwrap();
+        assert_eq!(v, vec![1, 2, 3]);
+
+        let v: Vec<String> = try_read!("{*};", "ab cd;".bytes()).unwrap();
+        assert_eq!(v, vec!["ab".to_string(), "cd".to_string()]);
+    }
+
+    #[test]
+    fn leading_whitespace_skip_before_literal() {
+        let x: i32 = try_read!("x ={ };", "x =   7;".bytes()).unwrap();
+        assert_eq!(x, 7);
+    }
+
+    #[test]
+    fn leading_whitespace_skip_before_comma() {
+        let x: i32 = try_read!("{ },", "   5,".bytes()).unwrap();
+        assert_eq!(x, 5);
+    }
+
+    #[test]
+    fn custom_delims_via_parse_capture() {
+        use crate::{parse_capture, CaptureOpts, CountingIter, Spec};
+
+        let mut bytes = "12,34;".bytes();
+        let mut iter = CountingIter::new(&mut bytes);
+        let opts = CaptureOpts {
+            delims: b",",
+            ..CaptureOpts::default()
+        };
+        let first: i32 = parse_capture("n", None, &mut iter, Spec::default(), opts).unwrap();
+        assert_eq!(first, 12);
+
+        let opts = CaptureOpts {
+            delims: b";",
+            ..CaptureOpts::default()
+        };
+        let second: i32 = parse_capture("n", None, &mut iter, Spec::default(), opts).unwrap();
+        assert_eq!(second, 34);
+    }
+
+    #[test]
+    fn error_carries_position() {
+        let err: Result<i32, _> = try_read!("{}", "nope".bytes());
+        let pos = err.unwrap_err().at().unwrap();
+        assert_eq!(pos.line, 1);
+        assert_eq!(pos.column, 5);
+    }
+
+    #[test]
+    fn invalid_utf8_reports_the_bad_byte() {
+        let bytes: Vec<u8> = vec![0xff, b' '];
+        let err: Result<String, _> = try_read!("{}", bytes.into_iter());
+        match err {
+            Err(Error::InvalidUtf8(raw, _)) => assert_eq!(raw, vec![0xff]),
+            other => panic!("expected InvalidUtf8, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn partial_utf8_keeps_the_valid_prefix() {
+        let bytes: Vec<u8> = vec![b'a', 0xff, b' '];
+        let err: Result<String, _> = try_read!("{}", bytes.into_iter());
+        match err {
+            Err(Error::PartialUtf8(valid_up_to, raw, _)) => {
+                assert_eq!(valid_up_to, 1);
+                assert_eq!(raw, vec![b'a', 0xff]);
+            }
+            other => panic!("expected PartialUtf8, got {:?}", other),
+        }
+    }
+}